@@ -0,0 +1,118 @@
+//! Content-addressed remote cache for the compact public keys generated by the `wasm_pk_gen`
+//! benchmark pipeline. Keying artifacts by a digest of their generating parameters (the same
+//! trick a compiler cache uses for build inputs) lets repeated benchmark/CI runs reuse a
+//! previously generated key instead of paying for FHE keygen again.
+//!
+//! This module is meant to be wired into the key generation step of the `wasm_pk_gen` benchmark
+//! itself (wrapping its compact public key generation with [`get_or_generate`]); that benchmark
+//! binary is not part of this crate's `examples/utilities` tree, so this module is not yet called
+//! from anywhere and is allowed to go unused until that call site pulls it in. Deliberately no
+//! concrete backend (S3 or otherwise) ships here: whichever benchmark adopts `RemoteCache` should
+//! implement it against whatever client it already depends on, rather than this example target
+//! pulling in a full cloud SDK for a backend nothing calls yet.
+
+use sha2::{Digest, Sha256};
+use tfhe::shortint::ClassicPBSParameters;
+
+/// Digest identifying a compact public key generated from a given `ClassicPBSParameters`, stable
+/// across runs and machines as long as the parameters and crate version are unchanged.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct CacheKey(String);
+
+impl CacheKey {
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+/// Hashes `params` together with the crate name and version into a `CacheKey`, so a parameter
+/// change or a crate upgrade invalidates the cache instead of serving a stale key.
+pub fn compute_cache_key(params: &ClassicPBSParameters) -> CacheKey {
+    let mut hasher = Sha256::new();
+    hasher
+        .update(serde_json::to_vec(params).expect("failed to serialize parameters for cache key"));
+    hasher.update(env!("CARGO_PKG_NAME").as_bytes());
+    hasher.update(env!("CARGO_PKG_VERSION").as_bytes());
+    CacheKey(format!("{:x}", hasher.finalize()))
+}
+
+/// A content-addressed remote object store. Implement this against whichever backend (S3, GCS, an
+/// internal blob store, ...) the integrating benchmark already talks to.
+pub trait RemoteCache {
+    /// Downloads the artifact stored under `key`, if present.
+    fn get(&self, key: &CacheKey) -> Option<Vec<u8>>;
+    /// Uploads `data` under `key`, making it available to future cache hits.
+    fn put(&self, key: &CacheKey, data: &[u8]);
+}
+
+/// Fetches the compact public key for `params` from `cache`, calling `generate` and uploading the
+/// result on a miss. `generate` should perform the (expensive) actual key generation.
+pub fn get_or_generate(
+    cache: &dyn RemoteCache,
+    params: &ClassicPBSParameters,
+    generate: impl FnOnce() -> Vec<u8>,
+) -> Vec<u8> {
+    let key = compute_cache_key(params);
+    if let Some(cached) = cache.get(&key) {
+        return cached;
+    }
+    let generated = generate();
+    cache.put(&key, &generated);
+    generated
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::{Cell, RefCell};
+    use std::collections::HashMap;
+    use tfhe::shortint::parameters::{
+        PARAM_MESSAGE_2_CARRY_2_COMPACT_PK, PARAM_SMALL_MESSAGE_2_CARRY_2_COMPACT_PK,
+    };
+
+    #[derive(Default)]
+    struct InMemoryCache {
+        objects: RefCell<HashMap<String, Vec<u8>>>,
+    }
+
+    impl RemoteCache for InMemoryCache {
+        fn get(&self, key: &CacheKey) -> Option<Vec<u8>> {
+            self.objects.borrow().get(key.as_str()).cloned()
+        }
+
+        fn put(&self, key: &CacheKey, data: &[u8]) {
+            self.objects
+                .borrow_mut()
+                .insert(key.as_str().to_string(), data.to_vec());
+        }
+    }
+
+    #[test]
+    fn cache_key_is_deterministic_and_parameter_sensitive() {
+        let key_a = compute_cache_key(&PARAM_MESSAGE_2_CARRY_2_COMPACT_PK);
+        let key_a_again = compute_cache_key(&PARAM_MESSAGE_2_CARRY_2_COMPACT_PK);
+        let key_b = compute_cache_key(&PARAM_SMALL_MESSAGE_2_CARRY_2_COMPACT_PK);
+
+        assert_eq!(key_a, key_a_again);
+        assert_ne!(key_a, key_b);
+    }
+
+    #[test]
+    fn get_or_generate_only_generates_once_per_key() {
+        let cache = InMemoryCache::default();
+        let generate_calls = Cell::new(0);
+
+        let first = get_or_generate(&cache, &PARAM_MESSAGE_2_CARRY_2_COMPACT_PK, || {
+            generate_calls.set(generate_calls.get() + 1);
+            vec![1, 2, 3]
+        });
+        let second = get_or_generate(&cache, &PARAM_MESSAGE_2_CARRY_2_COMPACT_PK, || {
+            generate_calls.set(generate_calls.get() + 1);
+            vec![9, 9, 9]
+        });
+
+        assert_eq!(first, vec![1, 2, 3]);
+        assert_eq!(second, vec![1, 2, 3]);
+        assert_eq!(generate_calls.get(), 1);
+    }
+}
@@ -1,48 +1,457 @@
 #[path = "../../benches/utilities.rs"]
 mod utilities;
+#[allow(dead_code)]
+mod pk_gen_remote_cache;
 
 use crate::utilities::{write_to_json, OperatorType};
-use clap::Parser;
-use std::collections::HashMap;
+use clap::{Parser, ValueEnum};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
 use std::fs;
 
 use std::fs::{File, OpenOptions};
 use std::io::Write;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use std::process::{Command, ExitCode};
+use std::sync::OnceLock;
 use tfhe::shortint::keycache::NamedParam;
-use tfhe::shortint::parameters::{
-    PARAM_MESSAGE_2_CARRY_2_COMPACT_PK, PARAM_SMALL_MESSAGE_2_CARRY_2_COMPACT_PK,
-};
+use tfhe::shortint::parameters::{ALL_PARAMETER_VEC, PBSParameters};
 use tfhe::shortint::ClassicPBSParameters;
 
 #[derive(Parser, Debug)]
 #[command(author, version, about, long_about = None)]
 struct Args {
-    raw_results_dir: String,
+    /// Required unless `--list-params` is given, in which case the parser only prints the known
+    /// parameter names and exits without needing a results directory.
+    #[arg(required_unless_present = "list_params")]
+    raw_results_dir: Option<String>,
+
+    /// Path to a `wasm_pk_gen.csv` produced by a previous run (e.g. the PR base revision) to
+    /// compare the current run against.
+    #[arg(long)]
+    baseline: Option<String>,
+
+    /// Maximum allowed slowdown, as a percentage of the baseline duration, before a benchmark is
+    /// reported as a regression.
+    #[arg(long, default_value_t = 10.0)]
+    regression_threshold: f64,
+
+    /// Path to a JSON metrics store that each run's results are appended to, keyed by timestamp
+    /// and git revision, so performance can be tracked over time rather than as a single snapshot.
+    #[arg(long)]
+    metrics_store: Option<String>,
+
+    /// Output formats to export parsed results to, e.g. `--format csv,prometheus`.
+    #[arg(long, value_delimiter = ',', default_values_t = [OutputFormat::Csv, OutputFormat::Json])]
+    format: Vec<OutputFormat>,
+
+    /// Print every shortint parameter set name known to this parser, then exit.
+    #[arg(long)]
+    list_params: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum OutputFormat {
+    Csv,
+    Json,
+    Toml,
+    Yaml,
+    Prometheus,
+}
+
+impl std::fmt::Display for OutputFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            self.to_possible_value()
+                .expect("OutputFormat has no skipped variants")
+                .get_name()
+        )
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+enum RegressionStatus {
+    Regression,
+    Improvement,
+    Unchanged,
+}
+
+#[derive(Debug, Serialize)]
+struct BenchComparison {
+    full_name: String,
+    baseline_ns: usize,
+    current_ns: usize,
+    delta_percent: f64,
+    status: RegressionStatus,
+}
+
+/// A single parsed benchmark measurement, as produced by one parser run.
+#[derive(Debug, Clone)]
+struct BenchMeasurement {
+    value_ns: usize,
+    param_name: String,
+}
+
+/// One entry of the persistent metrics store: a benchmark's measured duration together with the
+/// parameter set it was run with.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct MetricSample {
+    param_name: String,
+    value_ns: usize,
+}
+
+/// `run_key (timestamp + git revision) -> full_name -> sample`, serialized to `metrics.json` so
+/// that history accumulates across runs instead of being overwritten.
+type MetricsStore = BTreeMap<String, BTreeMap<String, MetricSample>>;
+
+fn git_revision() -> String {
+    std::env::var("GIT_COMMIT").ok().unwrap_or_else(|| {
+        Command::new("git")
+            .args(["rev-parse", "HEAD"])
+            .output()
+            .ok()
+            .filter(|output| output.status.success())
+            .and_then(|output| String::from_utf8(output.stdout).ok())
+            .map(|revision| revision.trim().to_string())
+            .unwrap_or_else(|| "unknown-revision".to_string())
+    })
+}
+
+/// Converts a day count since the Unix epoch into a proleptic-Gregorian `(year, month, day)`,
+/// using Howard Hinnant's `civil_from_days` algorithm.
+fn civil_from_days(days_since_epoch: i64) -> (i64, u32, u32) {
+    let z = days_since_epoch + 719_468;
+    let era = if z >= 0 { z } else { z - 146_096 } / 146_097;
+    let day_of_era = (z - era * 146_097) as u64;
+    let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096)
+        / 365;
+    let year = year_of_era as i64 + era * 400;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = (day_of_year - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = if month <= 2 { year + 1 } else { year };
+    (year, month, day)
+}
+
+/// Formats `unix_seconds` as an ISO-8601 UTC timestamp, computed in-process rather than by
+/// shelling out to the `date` binary.
+fn format_unix_timestamp(unix_seconds: u64) -> String {
+    const SECONDS_PER_DAY: u64 = 86_400;
+    let days_since_epoch = unix_seconds / SECONDS_PER_DAY;
+    let seconds_of_day = unix_seconds % SECONDS_PER_DAY;
+    let (year, month, day) = civil_from_days(days_since_epoch as i64);
+    let hour = seconds_of_day / 3600;
+    let minute = (seconds_of_day % 3600) / 60;
+    let second = seconds_of_day % 60;
+    format!("{year:04}-{month:02}-{day:02}T{hour:02}:{minute:02}:{second:02}Z")
+}
+
+fn current_timestamp() -> String {
+    let unix_seconds = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .expect("system clock is before the unix epoch")
+        .as_secs();
+    format_unix_timestamp(unix_seconds)
+}
+
+fn load_metrics_store(metrics_store_file: &Path) -> MetricsStore {
+    fs::read_to_string(metrics_store_file)
+        .ok()
+        .map(|raw| serde_json::from_str(&raw).expect("cannot parse existing metrics store"))
+        .unwrap_or_default()
+}
+
+/// Loads the metrics store at `metrics_store_file` (if any), appends `results` under a new
+/// `timestamp_revision` run key, and writes the merged, sorted store back to disk.
+fn append_to_metrics_store(
+    metrics_store_file: &Path,
+    results: &HashMap<String, BenchMeasurement>,
+) {
+    let mut store = load_metrics_store(metrics_store_file);
+
+    let run_key = format!("{}_{}", current_timestamp(), git_revision());
+    let run_entry = store.entry(run_key).or_default();
+    for (full_name, measurement) in results {
+        run_entry.insert(
+            full_name.clone(),
+            MetricSample {
+                param_name: measurement.param_name.clone(),
+                value_ns: measurement.value_ns,
+            },
+        );
+    }
+
+    let serialized =
+        serde_json::to_string_pretty(&store).expect("failed to serialize metrics store");
+    fs::write(metrics_store_file, serialized).expect("failed to write metrics store");
+}
+
+/// A single `(full_name, params, bench_name, value_in_ns)` tuple fed to every selected exporter.
+#[derive(Debug, Clone, Serialize)]
+struct ExportedSample {
+    full_name: String,
+    params_name: String,
+    bench_name: String,
+    value_ns: usize,
 }
 
-fn params_from_name(name: &str) -> ClassicPBSParameters {
-    match name.to_lowercase().as_str() {
-        "param_message_2_carry_2_compact_pk" => PARAM_MESSAGE_2_CARRY_2_COMPACT_PK,
-        "param_small_message_2_carry_2_compact_pk" => PARAM_SMALL_MESSAGE_2_CARRY_2_COMPACT_PK,
-        _ => panic!("failed to get parameters for name '{name}'"),
+/// A destination format for parsed benchmark results. Implementors receive every parsed sample
+/// through `export` and flush any buffered state through `finalize` once parsing is done.
+trait Exporter {
+    fn export(&mut self, sample: &ExportedSample);
+    fn finalize(&mut self) {}
+}
+
+struct CsvExporter {
+    file: File,
+}
+
+impl CsvExporter {
+    fn new(results_file: &Path) -> Self {
+        File::create(results_file).expect("create results file failed");
+        let file = OpenOptions::new()
+            .append(true)
+            .open(results_file)
+            .expect("cannot open parsed results file");
+        Self { file }
+    }
+}
+
+impl Exporter for CsvExporter {
+    fn export(&mut self, sample: &ExportedSample) {
+        write_result(&mut self.file, &sample.full_name, sample.value_ns);
+    }
+}
+
+struct JsonExporter {
+    operator: OperatorType,
+}
+
+impl Exporter for JsonExporter {
+    fn export(&mut self, sample: &ExportedSample) {
+        write_to_json(
+            &sample.full_name,
+            params_from_name(&sample.params_name).expect("failed to look up parameter set"),
+            sample.params_name.clone(),
+            &sample.bench_name,
+            &self.operator,
+            0,
+            vec![],
+        );
+    }
+}
+
+struct PrometheusExporter {
+    output_file: PathBuf,
+    lines: Vec<String>,
+}
+
+impl Exporter for PrometheusExporter {
+    fn export(&mut self, sample: &ExportedSample) {
+        self.lines.push(format!(
+            "tfhe_bench_ns{{bench=\"{}\",params=\"{}\"}} {}",
+            sample.bench_name, sample.params_name, sample.value_ns
+        ));
+    }
+
+    fn finalize(&mut self) {
+        fs::write(&self.output_file, format!("{}\n", self.lines.join("\n")))
+            .expect("cannot write prometheus exposition file");
+    }
+}
+
+struct TomlExporter {
+    output_file: PathBuf,
+    samples: Vec<ExportedSample>,
+}
+
+impl Exporter for TomlExporter {
+    fn export(&mut self, sample: &ExportedSample) {
+        self.samples.push(sample.clone());
+    }
+
+    fn finalize(&mut self) {
+        #[derive(Serialize)]
+        struct TomlReport {
+            sample: Vec<ExportedSample>,
+        }
+        let serialized = toml::to_string_pretty(&TomlReport {
+            sample: self.samples.clone(),
+        })
+        .expect("cannot serialize benchmark results to toml");
+        fs::write(&self.output_file, serialized).expect("cannot write toml results file");
     }
 }
 
+struct YamlExporter {
+    output_file: PathBuf,
+    samples: Vec<ExportedSample>,
+}
+
+impl Exporter for YamlExporter {
+    fn export(&mut self, sample: &ExportedSample) {
+        self.samples.push(sample.clone());
+    }
+
+    fn finalize(&mut self) {
+        // serde_yaml was archived in March 2024; serde_norway is its maintained continuation.
+        let serialized = serde_norway::to_string(&self.samples)
+            .expect("cannot serialize benchmark results to yaml");
+        fs::write(&self.output_file, serialized).expect("cannot write yaml results file");
+    }
+}
+
+fn build_exporters(formats: &[OutputFormat], results_file: &Path) -> Vec<Box<dyn Exporter>> {
+    formats
+        .iter()
+        .map(|format| -> Box<dyn Exporter> {
+            match format {
+                OutputFormat::Csv => Box::new(CsvExporter::new(results_file)),
+                OutputFormat::Json => Box::new(JsonExporter {
+                    operator: OperatorType::Atomic,
+                }),
+                OutputFormat::Prometheus => Box::new(PrometheusExporter {
+                    output_file: results_file.with_extension("prom"),
+                    lines: vec![],
+                }),
+                OutputFormat::Toml => Box::new(TomlExporter {
+                    output_file: results_file.with_extension("toml"),
+                    samples: vec![],
+                }),
+                OutputFormat::Yaml => Box::new(YamlExporter {
+                    output_file: results_file.with_extension("yaml"),
+                    samples: vec![],
+                }),
+            }
+        })
+        .collect()
+}
+
+/// Raised by [`params_from_name`] when asked for a parameter set not present in [`ALL_PARAMETER_VEC`].
+#[derive(Debug, Clone)]
+struct UnknownParamError(String);
+
+impl std::fmt::Display for UnknownParamError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "unknown shortint parameter set '{}'", self.0)
+    }
+}
+
+impl std::error::Error for UnknownParamError {}
+
+/// `name() -> ClassicPBSParameters` for every classic PBS parameter set in [`ALL_PARAMETER_VEC`],
+/// built once and indexed by the lowercased canonical name so new parameter sets are picked up
+/// without editing this file.
+fn param_registry() -> &'static HashMap<String, ClassicPBSParameters> {
+    static REGISTRY: OnceLock<HashMap<String, ClassicPBSParameters>> = OnceLock::new();
+    REGISTRY.get_or_init(|| {
+        ALL_PARAMETER_VEC
+            .iter()
+            .filter_map(|params| match params {
+                PBSParameters::PBS(classic) => Some((classic.name().to_lowercase(), *classic)),
+                _ => None,
+            })
+            .collect()
+    })
+}
+
+fn params_from_name(name: &str) -> Result<ClassicPBSParameters, UnknownParamError> {
+    param_registry()
+        .get(&name.to_lowercase())
+        .copied()
+        .ok_or_else(|| UnknownParamError(name.to_string()))
+}
+
+fn list_known_param_names() -> Vec<&'static str> {
+    let mut names: Vec<&'static str> = param_registry().keys().map(String::as_str).collect();
+    names.sort_unstable();
+    names
+}
+
 fn write_result(file: &mut File, name: &str, value: usize) {
     let line = format!("{name},{value}\n");
     let error_message = format!("cannot write {name} result into file");
     file.write_all(line.as_bytes()).expect(&error_message);
 }
 
-pub fn parse_wasm_benchmarks(results_file: &Path, raw_results_dir: &Path) {
-    File::create(results_file).expect("create results file failed");
-    let mut file = OpenOptions::new()
-        .append(true)
-        .open(results_file)
-        .expect("cannot open parsed results file");
+/// Parses a previously generated `wasm_pk_gen.csv` into a `full_name -> nanoseconds` map.
+fn load_baseline_results(baseline_file: &Path) -> HashMap<String, usize> {
+    let raw = fs::read_to_string(baseline_file).expect("cannot open baseline results file");
+    raw.lines()
+        .filter_map(|line| {
+            let (name, value) = line.split_once(',')?;
+            Some((name.to_string(), value.parse().ok()?))
+        })
+        .collect()
+}
+
+/// Compares `current` against `baseline`, returning one `BenchComparison` per benchmark present
+/// in both runs and whether any of them regressed beyond `regression_threshold` percent.
+fn compare_against_baseline(
+    current: &HashMap<String, BenchMeasurement>,
+    baseline: &HashMap<String, usize>,
+    regression_threshold: f64,
+) -> (Vec<BenchComparison>, bool) {
+    let mut comparisons: Vec<BenchComparison> = current
+        .iter()
+        .filter_map(|(full_name, measurement)| {
+            let current_ns = measurement.value_ns;
+            let &baseline_ns = baseline.get(full_name)?;
+            let delta_percent =
+                (current_ns as f64 - baseline_ns as f64) / baseline_ns as f64 * 100.0;
+            let status = if delta_percent > regression_threshold {
+                RegressionStatus::Regression
+            } else if delta_percent < -regression_threshold {
+                RegressionStatus::Improvement
+            } else {
+                RegressionStatus::Unchanged
+            };
+            Some(BenchComparison {
+                full_name: full_name.clone(),
+                baseline_ns,
+                current_ns,
+                delta_percent,
+                status,
+            })
+        })
+        .collect();
+    comparisons.sort_by(|a, b| a.full_name.cmp(&b.full_name));
+
+    let has_regression = comparisons
+        .iter()
+        .any(|c| c.status == RegressionStatus::Regression);
+    (comparisons, has_regression)
+}
+
+fn print_comparison_report(comparisons: &[BenchComparison]) {
+    println!(
+        "{:<60} {:>15} {:>15} {:>10} {:>12}",
+        "benchmark", "baseline (ns)", "current (ns)", "delta %", "status"
+    );
+    for comparison in comparisons {
+        println!(
+            "{:<60} {:>15} {:>15} {:>10.2} {:>12?}",
+            comparison.full_name,
+            comparison.baseline_ns,
+            comparison.current_ns,
+            comparison.delta_percent,
+            comparison.status
+        );
+    }
+
+    let summary = serde_json::to_string_pretty(comparisons)
+        .expect("failed to serialize regression comparison summary");
+    println!("{summary}");
+}
 
-    let operator = OperatorType::Atomic;
+pub fn parse_wasm_benchmarks(
+    raw_results_dir: &Path,
+    exporters: &mut [Box<dyn Exporter>],
+) -> HashMap<String, BenchMeasurement> {
+    let mut results = HashMap::new();
 
     for entry in raw_results_dir
         .read_dir()
@@ -55,33 +464,232 @@ pub fn parse_wasm_benchmarks(results_file: &Path, raw_results_dir: &Path) {
         for (full_name, val) in results_as_json.iter() {
             let name_parts = full_name.split("_mean_").collect::<Vec<_>>();
             let bench_name = name_parts[0];
-            let params = params_from_name(name_parts[1]);
+            let params = params_from_name(name_parts[1])
+                .unwrap_or_else(|err| panic!("failed to parse benchmark '{full_name}': {err}"));
             let value_in_ns = (val * 1_000_000_f32) as usize;
 
-            write_result(&mut file, full_name, value_in_ns);
-            write_to_json(
-                full_name,
-                params,
-                params.name(),
-                bench_name,
-                &operator,
-                0,
-                vec![],
+            let sample = ExportedSample {
+                full_name: full_name.clone(),
+                params_name: params.name().to_string(),
+                bench_name: bench_name.to_string(),
+                value_ns: value_in_ns,
+            };
+            for exporter in exporters.iter_mut() {
+                exporter.export(&sample);
+            }
+
+            results.insert(
+                full_name.clone(),
+                BenchMeasurement {
+                    value_ns: value_in_ns,
+                    param_name: params.name().to_string(),
+                },
             );
         }
     }
+
+    for exporter in exporters.iter_mut() {
+        exporter.finalize();
+    }
+
+    results
 }
 
-fn main() {
+fn main() -> ExitCode {
     let args = Args::parse();
 
+    if args.list_params {
+        for name in list_known_param_names() {
+            println!("{name}");
+        }
+        return ExitCode::SUCCESS;
+    }
+
     let work_dir = std::env::current_dir().unwrap();
     let mut new_work_dir = work_dir;
     new_work_dir.push("tfhe");
     std::env::set_current_dir(new_work_dir).unwrap();
 
     let results_file = Path::new("wasm_pk_gen.csv");
-    let raw_results_dir = Path::new(&args.raw_results_dir);
+    let raw_results_dir = args
+        .raw_results_dir
+        .as_deref()
+        .expect("raw_results_dir is required unless --list-params is set");
+    let raw_results_dir = Path::new(raw_results_dir);
+
+    let mut exporters = build_exporters(&args.format, results_file);
+    let results = parse_wasm_benchmarks(raw_results_dir, &mut exporters);
+
+    if let Some(metrics_store) = &args.metrics_store {
+        append_to_metrics_store(Path::new(metrics_store), &results);
+    }
+
+    let Some(baseline) = args.baseline else {
+        return ExitCode::SUCCESS;
+    };
+
+    let baseline_results = load_baseline_results(Path::new(&baseline));
+    let (comparisons, has_regression) =
+        compare_against_baseline(&results, &baseline_results, args.regression_threshold);
+    print_comparison_report(&comparisons);
+
+    if has_regression {
+        eprintln!(
+            "one or more benchmarks regressed beyond the {}% threshold",
+            args.regression_threshold
+        );
+        return ExitCode::FAILURE;
+    }
+
+    ExitCode::SUCCESS
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-    parse_wasm_benchmarks(results_file, raw_results_dir);
-}
\ No newline at end of file
+    #[test]
+    fn params_from_name_resolves_known_names_case_insensitively() {
+        assert!(params_from_name("PARAM_MESSAGE_2_CARRY_2_COMPACT_PK").is_ok());
+        assert!(params_from_name("param_message_2_carry_2_compact_pk").is_ok());
+    }
+
+    #[test]
+    fn params_from_name_rejects_unknown_names() {
+        assert!(params_from_name("not_a_real_parameter_set").is_err());
+    }
+
+    #[test]
+    fn list_known_param_names_is_sorted_and_non_empty() {
+        let names = list_known_param_names();
+        assert!(!names.is_empty());
+        let mut sorted = names.clone();
+        sorted.sort_unstable();
+        assert_eq!(names, sorted);
+    }
+
+    fn measurement(value_ns: usize) -> BenchMeasurement {
+        BenchMeasurement {
+            value_ns,
+            param_name: "param_message_2_carry_2_compact_pk".to_string(),
+        }
+    }
+
+    #[test]
+    fn compare_against_baseline_classifies_regressions_and_improvements() {
+        let current = HashMap::from([
+            ("slower".to_string(), measurement(150)),
+            ("faster".to_string(), measurement(50)),
+            ("same".to_string(), measurement(101)),
+        ]);
+        let baseline = HashMap::from([
+            ("slower".to_string(), 100),
+            ("faster".to_string(), 100),
+            ("same".to_string(), 100),
+        ]);
+
+        let (comparisons, has_regression) = compare_against_baseline(&current, &baseline, 10.0);
+        let by_name: HashMap<_, _> = comparisons
+            .iter()
+            .map(|c| (c.full_name.as_str(), c.status))
+            .collect();
+
+        assert_eq!(by_name["slower"], RegressionStatus::Regression);
+        assert_eq!(by_name["faster"], RegressionStatus::Improvement);
+        assert_eq!(by_name["same"], RegressionStatus::Unchanged);
+        assert!(has_regression);
+    }
+
+    #[test]
+    fn compare_against_baseline_ignores_benchmarks_missing_from_either_run() {
+        let current = HashMap::from([("only_in_current".to_string(), measurement(100))]);
+        let baseline = HashMap::from([("only_in_baseline".to_string(), 100)]);
+
+        let (comparisons, has_regression) = compare_against_baseline(&current, &baseline, 10.0);
+
+        assert!(comparisons.is_empty());
+        assert!(!has_regression);
+    }
+
+    #[test]
+    fn load_baseline_results_round_trips_csv_written_by_write_result() {
+        let dir = std::env::temp_dir().join(format!(
+            "wasm_benchmarks_parser_test_{:?}",
+            std::thread::current().id()
+        ));
+        fs::create_dir_all(&dir).expect("failed to create temp dir for test");
+        let baseline_file = dir.join("baseline.csv");
+
+        let mut file = File::create(&baseline_file).expect("failed to create baseline file");
+        write_result(&mut file, "bench_a_mean_param_message_2_carry_2_compact_pk", 123);
+        write_result(
+            &mut file,
+            "bench_b_mean_param_small_message_2_carry_2_compact_pk",
+            456,
+        );
+        drop(file);
+
+        let results = load_baseline_results(&baseline_file);
+
+        assert_eq!(
+            results["bench_a_mean_param_message_2_carry_2_compact_pk"],
+            123
+        );
+        assert_eq!(
+            results["bench_b_mean_param_small_message_2_carry_2_compact_pk"],
+            456
+        );
+
+        fs::remove_dir_all(&dir).ok();
+    }
+
+    fn temp_metrics_store_path(test_name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!(
+            "wasm_benchmarks_parser_metrics_{test_name}_{:?}.json",
+            std::thread::current().id()
+        ))
+    }
+
+    #[test]
+    fn append_to_metrics_store_merges_into_existing_runs_and_sorts_keys() {
+        let store_file = temp_metrics_store_path("merge_and_sort");
+        fs::remove_file(&store_file).ok();
+
+        let mut first_run = HashMap::new();
+        first_run.insert("bench_b".to_string(), measurement(200));
+        append_to_metrics_store(&store_file, &first_run);
+
+        let store_after_first_run = load_metrics_store(&store_file);
+        assert_eq!(store_after_first_run.len(), 1);
+        let run_key = store_after_first_run.keys().next().unwrap().clone();
+        assert_eq!(
+            store_after_first_run[&run_key]["bench_b"].value_ns,
+            200
+        );
+
+        let mut second_run = HashMap::new();
+        second_run.insert("bench_a".to_string(), measurement(50));
+        append_to_metrics_store(&store_file, &second_run);
+
+        // The second run lands under a run key at or after the first one (the same key when both
+        // runs share a timestamp second and revision), and the first run's data is kept.
+        let merged = load_metrics_store(&store_file);
+        let keys: Vec<_> = merged.keys().collect();
+        let mut sorted_keys = keys.clone();
+        sorted_keys.sort();
+        assert_eq!(keys, sorted_keys);
+        let (_, latest_run) = merged.iter().next_back().unwrap();
+        assert_eq!(latest_run["bench_a"].value_ns, 50);
+        assert_eq!(merged[&run_key]["bench_b"].value_ns, 200);
+
+        fs::remove_file(&store_file).ok();
+    }
+
+    #[test]
+    fn load_metrics_store_returns_empty_store_when_file_is_missing() {
+        let store_file = temp_metrics_store_path("missing_file");
+        fs::remove_file(&store_file).ok();
+
+        assert!(load_metrics_store(&store_file).is_empty());
+    }
+}